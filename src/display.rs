@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+/// Where a SPICE server should listen.
+#[derive(Debug, Clone)]
+pub enum SpiceTarget {
+    Port(u16),
+    UnixSocket(PathBuf),
+}
+
+/// A looking-glass shared-memory device, sized for `width`x`height`.
+#[derive(Debug, Clone)]
+pub struct LookingGlass {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Display/audio backends to attach to a VM's QEMU command line, on top of
+/// its headless default.
+#[derive(Debug, Clone, Default)]
+pub struct DisplaySettings {
+    pub spice: Option<SpiceTarget>,
+    pub pulseaudio: bool,
+    pub looking_glass: Option<LookingGlass>,
+}
+
+impl DisplaySettings {
+    /// Builds the QEMU arguments these settings require. Returns an empty
+    /// list when nothing is set, leaving `start`'s headless default in
+    /// place.
+    pub fn qemu_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        match &self.spice {
+            Some(SpiceTarget::Port(port)) => {
+                args.push("-spice".to_string());
+                args.push(format!("port={},disable-ticketing=on", port));
+            }
+            Some(SpiceTarget::UnixSocket(path)) => {
+                args.push("-spice".to_string());
+                args.push(format!("unix=on,addr={},disable-ticketing=on", path.display()));
+            }
+            None => {}
+        }
+
+        if self.pulseaudio {
+            args.push("-audiodev".to_string());
+            args.push("pa,id=vml-pa".to_string());
+            args.push("-device".to_string());
+            args.push("intel-hda".to_string());
+            args.push("-device".to_string());
+            args.push("hda-duplex,audiodev=vml-pa".to_string());
+        }
+
+        if let Some(looking_glass) = &self.looking_glass {
+            args.push("-device".to_string());
+            args.push("ivshmem-plain,memdev=looking-glass".to_string());
+            args.push("-object".to_string());
+            args.push(format!(
+                "memory-backend-file,id=looking-glass,mem-path=/dev/shm/looking-glass,size={},share=on",
+                looking_glass_shm_size(looking_glass.width, looking_glass.height)
+            ));
+        }
+
+        args
+    }
+}
+
+/// The shared-memory size looking-glass needs for `width`x`height`: two
+/// full frames at 4 bytes/pixel, rounded up to the next MiB, plus its
+/// fixed 10MiB header.
+fn looking_glass_shm_size(width: u32, height: u32) -> u64 {
+    let frame_bytes = u64::from(width) * u64::from(height) * 4 * 2;
+    let mib = 1024 * 1024;
+    let rounded = frame_bytes.div_ceil(mib) * mib;
+    rounded + 10 * mib
+}
@@ -0,0 +1,64 @@
+use std::fmt;
+
+pub type Result<T> = anyhow::Result<T>;
+
+#[derive(Debug)]
+pub enum Error {
+    DownloadImage(String),
+    UnknownImage(String),
+    ImageDoesNotExists(String),
+    GetWrongEmbeddedFile(String),
+    Executable(String, String),
+    SSHFailed(String),
+    ChecksumMismatch { expected: String, actual: String },
+    ChecksumNotFound(String),
+    InvalidChecksum(String),
+    InvalidAuth(String),
+    UnsupportedTransform(String),
+    Multiple(Vec<String>),
+    LuaHook(String),
+    QmpProtocol(String),
+    QmpError { class: String, desc: String },
+    UnknownHostGroup(String),
+}
+
+impl Error {
+    pub fn executable(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Error::Executable(name.into(), message.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::DownloadImage(message) => write!(f, "failed to download image: {}", message),
+            Error::UnknownImage(name) => write!(f, "unknown image `{}`", name),
+            Error::ImageDoesNotExists(name) => write!(f, "image `{}` does not exist", name),
+            Error::GetWrongEmbeddedFile(path) => {
+                write!(f, "failed to get embedded file `{}`", path)
+            }
+            Error::Executable(name, message) => {
+                write!(f, "failed to execute `{}`: {}", name, message)
+            }
+            Error::SSHFailed(name) => write!(f, "ssh to `{}` failed", name),
+            Error::ChecksumMismatch { expected, actual } => {
+                write!(f, "checksum mismatch: expected `{}`, got `{}`", expected, actual)
+            }
+            Error::ChecksumNotFound(name) => {
+                write!(f, "no checksum found for image `{}` in checksum file", name)
+            }
+            Error::InvalidChecksum(value) => write!(f, "invalid checksum `{}`", value),
+            Error::InvalidAuth(message) => write!(f, "invalid image auth: {}", message),
+            Error::UnsupportedTransform(step) => write!(f, "unsupported image transform `{}`", step),
+            Error::Multiple(messages) => {
+                write!(f, "{} command(s) failed:\n{}", messages.len(), messages.join("\n"))
+            }
+            Error::LuaHook(message) => write!(f, "build hook failed: {}", message),
+            Error::QmpProtocol(message) => write!(f, "QMP protocol error: {}", message),
+            Error::QmpError { class, desc } => write!(f, "QMP command failed ({}): {}", class, desc),
+            Error::UnknownHostGroup(name) => write!(f, "unknown host group `{}`", name),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -5,17 +5,21 @@ use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::env::consts::ARCH;
 use std::fs;
 use std::fs::OpenOptions;
+use std::io;
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
 use std::time::{Duration, SystemTime};
 
 use anyhow::{bail, Context, Result};
-use cmd_lib::run_fun;
+use cmd_lib::{run_cmd, run_fun};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
 
 use crate::config::Images as ConfigImages;
 use crate::config_dir;
 use crate::files;
+use crate::jobs;
 use crate::template;
 use crate::Error;
 
@@ -27,6 +31,112 @@ pub struct Image<'a> {
     get_url_prog: Option<PathBuf>,
     config: &'a ConfigImages,
     update_after_days: Option<u64>,
+    checksum: Option<String>,
+    checksum_url: Option<String>,
+    auth: Option<DeserializeAuth>,
+    transform: Vec<DeserializeTransform>,
+}
+
+/// Resolved credentials for an authenticated image source, ready to attach
+/// to a request.
+#[derive(Clone, Debug)]
+enum Credential {
+    Bearer(String),
+    Basic { user: String, password: String },
+}
+
+/// A checksum algorithm vml knows how to verify downloads against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ChecksumAlgorithm {
+    Sha256,
+    Sha512,
+}
+
+impl ChecksumAlgorithm {
+    fn from_digest_len(len: usize) -> Result<Self> {
+        match len {
+            64 => Ok(ChecksumAlgorithm::Sha256),
+            128 => Ok(ChecksumAlgorithm::Sha512),
+            _ => bail!(Error::InvalidChecksum(format!("unsupported digest length {}", len))),
+        }
+    }
+}
+
+/// The checksum a downloaded image is expected to match, resolved from either
+/// a literal `checksum` value or a fetched `checksum-url` sidecar file.
+#[derive(Clone, Debug)]
+struct ExpectedChecksum {
+    algorithm: ChecksumAlgorithm,
+    digest: String,
+}
+
+impl ExpectedChecksum {
+    fn parse(value: &str) -> Result<Self> {
+        let (algorithm, digest) =
+            value.split_once(':').ok_or_else(|| Error::InvalidChecksum(value.to_string()))?;
+        let algorithm = match algorithm {
+            "sha256" => ChecksumAlgorithm::Sha256,
+            "sha512" => ChecksumAlgorithm::Sha512,
+            other => bail!(Error::InvalidChecksum(format!("unknown algorithm `{}`", other))),
+        };
+
+        Ok(ExpectedChecksum { algorithm, digest: digest.to_lowercase() })
+    }
+
+    fn from_bare_digest(digest: &str) -> Result<Self> {
+        let algorithm = ChecksumAlgorithm::from_digest_len(digest.len())?;
+
+        Ok(ExpectedChecksum { algorithm, digest: digest.to_lowercase() })
+    }
+}
+
+/// A running hash for whichever [`ChecksumAlgorithm`] an image is checked
+/// against.
+enum Hasher {
+    Sha256(Box<Sha256>),
+    Sha512(Box<Sha512>),
+}
+
+impl Hasher {
+    fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Sha256 => Hasher::Sha256(Box::new(Sha256::new())),
+            ChecksumAlgorithm::Sha512 => Hasher::Sha512(Box::new(Sha512::new())),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Sha512(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            Hasher::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Sha512(hasher) => format!("{:x}", hasher.finalize()),
+        }
+    }
+}
+
+/// A `Write` adapter that feeds every byte written to the inner writer into a
+/// running hash, so a download can be verified without a second read pass.
+struct HashingWriter<'w, W> {
+    inner: &'w mut W,
+    hasher: &'w mut Hasher,
+}
+
+impl<'w, W: Write> Write for HashingWriter<'w, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
 }
 
 impl<'a> Image<'a> {
@@ -55,6 +165,10 @@ impl<'a> Image<'a> {
             description: image.description,
             config,
             update_after_days: image.update_after_days,
+            checksum: image.checksum,
+            checksum_url: image.checksum_url,
+            auth: image.auth,
+            transform: image.transform,
         }
     }
 
@@ -103,21 +217,296 @@ impl<'a> Image<'a> {
         url
     }
 
+    /// Resolves the checksum this image's download must match, if any was
+    /// configured either directly or via a `checksum-url` sidecar file.
+    fn expected_checksum(&self) -> Result<Option<ExpectedChecksum>> {
+        if let Some(checksum) = &self.checksum {
+            return Ok(Some(ExpectedChecksum::parse(checksum)?));
+        }
+
+        if let Some(checksum_url) = &self.checksum_url {
+            let digest = self.fetch_checksum_from_url(checksum_url)?;
+            return Ok(Some(ExpectedChecksum::from_bare_digest(&digest)?));
+        }
+
+        Ok(None)
+    }
+
+    /// Fetches a `SHA256SUMS`-style sidecar file and returns the hex digest
+    /// of the line matching this image's filename.
+    fn fetch_checksum_from_url(&self, checksum_url: &str) -> Result<String> {
+        let body = reqwest::blocking::get(checksum_url)
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| Error::DownloadImage(e.to_string()))?
+            .text()
+            .map_err(|e| Error::DownloadImage(e.to_string()))?;
+
+        let url = self.url();
+        let basename = url.rsplit('/').next().unwrap_or(&url);
+
+        for line in body.lines() {
+            let mut fields = line.split_whitespace();
+            if let (Some(digest), Some(filename)) = (fields.next(), fields.next()) {
+                if filename.trim_start_matches('*') == basename {
+                    return Ok(digest.to_string());
+                }
+            }
+        }
+
+        bail!(Error::ChecksumNotFound(self.name.clone()))
+    }
+
     pub fn pull(&self) -> Result<PathBuf> {
+        self.pull_reporting(None)
+    }
+
+    fn partial_path(&self) -> PathBuf {
+        self.config.directory.join(format!("{}.part", self.name))
+    }
+
+    /// Resolves this image's `auth` block (falling back to the config-level
+    /// default shared by every image) into concrete credentials, reading
+    /// token/password files and `netrc`-style credential files as needed.
+    /// Only the paths or inline values from `images.toml` are ever held
+    /// here; resolved secrets are never written back by `update_images`.
+    fn resolved_auth(&self) -> Result<Option<Credential>> {
+        let auth = match self.auth.as_ref().or(self.config.default_auth.as_ref()) {
+            Some(auth) => auth,
+            None => return Ok(None),
+        };
+
+        if let Some(bearer) = &auth.bearer {
+            let token = match (&bearer.token, &bearer.token_file) {
+                (Some(token), _) => token.clone(),
+                (None, Some(path)) => fs::read_to_string(path)
+                    .with_context(|| format!("failed to read bearer token file `{}`", path.display()))?
+                    .trim()
+                    .to_string(),
+                (None, None) => bail!(Error::InvalidAuth("bearer auth needs a token or token-file".into())),
+            };
+            return Ok(Some(Credential::Bearer(token)));
+        }
+
+        if let Some(basic) = &auth.basic {
+            let password = match (&basic.password, &basic.password_file) {
+                (Some(password), _) => password.clone(),
+                (None, Some(path)) => fs::read_to_string(path)
+                    .with_context(|| format!("failed to read password file `{}`", path.display()))?
+                    .trim()
+                    .to_string(),
+                (None, None) => {
+                    bail!(Error::InvalidAuth("basic auth needs a password or password-file".into()))
+                }
+            };
+            return Ok(Some(Credential::Basic { user: basic.user.clone(), password }));
+        }
+
+        if let Some(netrc) = &auth.netrc {
+            let host = reqwest::Url::parse(&self.url)
+                .ok()
+                .and_then(|u| u.host_str().map(str::to_string))
+                .ok_or_else(|| Error::InvalidAuth(format!("cannot determine host for `{}`", self.url)))?;
+            return netrc_credential(netrc, &host);
+        }
+
+        Ok(None)
+    }
+
+    /// Downloads the image, optionally sending [`jobs::Report`] progress
+    /// updates as bytes arrive so a multi-image pull can render them all.
+    ///
+    /// The download lands in a stable `<name>.part` file rather than an
+    /// anonymous tempfile, and resumes from its current length with a
+    /// `Range` request when one is already present, so an interrupted pull
+    /// of a large image doesn't restart from zero.
+    fn pull_reporting(&self, progress: Option<&Sender<jobs::Report>>) -> Result<PathBuf> {
         let url = &self.url();
-        let mut body =
-            reqwest::blocking::get(url).map_err(|e| Error::DownloadImage(e.to_string()))?;
         let image_path = self.path();
-        let images_dir = &self.config.directory;
-        let mut tmp = tempfile::Builder::new().tempfile_in(images_dir)?;
+        let partial_path = self.partial_path();
+        let resume_from = fs::metadata(&partial_path).map(|m| m.len()).unwrap_or(0);
+
+        let client = reqwest::blocking::Client::new();
+        let mut request = client.get(url);
+        request = match self.resolved_auth()? {
+            Some(Credential::Bearer(token)) => request.bearer_auth(token),
+            Some(Credential::Basic { user, password }) => request.basic_auth(user, Some(password)),
+            None => request,
+        };
+        if resume_from > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", resume_from));
+        }
+        let body = request.send().map_err(|e| Error::DownloadImage(e.to_string()))?;
 
-        println!("Downloading image {} {}", &self.name, url);
-        body.copy_to(&mut tmp).map_err(|e| Error::DownloadImage(e.to_string()))?;
+        let expected = self.expected_checksum()?;
+        let mut hasher = expected.as_ref().map(|e| Hasher::new(e.algorithm));
+
+        if progress.is_none() {
+            println!("Downloading image {} {}", &self.name, url);
+        }
+        self.report(progress, jobs::TaskStatus::Queued);
+
+        match body.status() {
+            reqwest::StatusCode::PARTIAL_CONTENT => {
+                if let Some(hasher) = hasher.as_mut() {
+                    Self::hash_file(&partial_path, hasher)?;
+                }
+                let mut partial = OpenOptions::new().append(true).open(&partial_path)?;
+                self.download_into(body, &mut partial, hasher.as_mut(), progress)?;
+            }
+            reqwest::StatusCode::RANGE_NOT_SATISFIABLE => {
+                if let Some(hasher) = hasher.as_mut() {
+                    Self::hash_file(&partial_path, hasher)?;
+                }
+            }
+            _ => {
+                let mut partial =
+                    OpenOptions::new().create(true).write(true).truncate(true).open(&partial_path)?;
+                self.download_into(body, &mut partial, hasher.as_mut(), progress)?;
+            }
+        }
+
+        if let (Some(expected), Some(hasher)) = (expected, hasher) {
+            self.report(progress, jobs::TaskStatus::Verifying);
+            let actual = hasher.finalize_hex();
+            if actual != expected.digest {
+                fs::remove_file(&partial_path).ok();
+                bail!(Error::ChecksumMismatch { expected: expected.digest, actual });
+            }
+        }
+
+        // Checksums guard the network bytes, but transforms still run
+        // against distinct output files rather than the `.part` file
+        // itself: `.part` is what a resumed pull appends to with a `Range`
+        // request, so if a transform dies partway through, `.part` must
+        // still hold exactly the verified download, not a half-decompressed
+        // or half-converted mess that the next run would blindly append to.
+        let mut current = partial_path.clone();
+        for step in &self.transform {
+            let next = self.apply_transform(&current, step)?;
+            if current != partial_path {
+                fs::remove_file(&current).ok();
+            }
+            current = next;
+        }
 
-        fs::rename(tmp.path(), &image_path)?;
+        fs::rename(&current, &image_path)?;
+        if current != partial_path {
+            fs::remove_file(&partial_path).ok();
+        }
+        self.report(progress, jobs::TaskStatus::Done);
 
         Ok(image_path)
     }
+
+    fn apply_transform(&self, path: &Path, step: &DeserializeTransform) -> Result<PathBuf> {
+        let mut current = path.to_path_buf();
+        if let Some(format) = &step.decompress {
+            let next = self.decompress(&current, format)?;
+            if current != path {
+                fs::remove_file(&current).ok();
+            }
+            current = next;
+        }
+        if let Some(target_format) = &step.convert_to {
+            let next = self.convert(&current, target_format)?;
+            if current != path {
+                fs::remove_file(&current).ok();
+            }
+            current = next;
+        }
+
+        Ok(current)
+    }
+
+    fn decompress(&self, path: &Path, format: &str) -> Result<PathBuf> {
+        let input = fs::File::open(path)?;
+        let output_path = path.with_extension("decompressed");
+        let mut output = fs::File::create(&output_path)?;
+
+        match format {
+            "xz" => io::copy(&mut xz2::read::XzDecoder::new(input), &mut output)?,
+            "gz" => io::copy(&mut flate2::read::GzDecoder::new(input), &mut output)?,
+            "zstd" => io::copy(&mut zstd::stream::read::Decoder::new(input)?, &mut output)?,
+            other => bail!(Error::UnsupportedTransform(format!("decompress = \"{}\"", other))),
+        };
+
+        Ok(output_path)
+    }
+
+    fn convert(&self, path: &Path, target_format: &str) -> Result<PathBuf> {
+        if !matches!(target_format, "qcow2" | "raw") {
+            bail!(Error::UnsupportedTransform(format!("convert-to = \"{}\"", target_format)));
+        }
+
+        let output_path = path.with_extension(format!("{}.converted", target_format));
+        run_cmd!(qemu-img convert -O $target_format $path $output_path)?;
+
+        Ok(output_path)
+    }
+
+    fn download_into(
+        &self,
+        body: reqwest::blocking::Response,
+        partial: &mut fs::File,
+        hasher: Option<&mut Hasher>,
+        progress: Option<&Sender<jobs::Report>>,
+    ) -> Result<()> {
+        if let Some(hasher) = hasher {
+            let mut writer = HashingWriter { inner: partial, hasher };
+            self.stream_to(body, &mut writer, progress)
+        } else {
+            self.stream_to(body, partial, progress)
+        }
+    }
+
+    /// Feeds an already-downloaded file's bytes into `hasher`, used when
+    /// resuming picks up checksum coverage for bytes written by a previous
+    /// run.
+    fn hash_file(path: &Path, hasher: &mut Hasher) -> Result<()> {
+        let mut file = fs::File::open(path)?;
+        let mut buf = [0u8; 64 * 1024];
+
+        loop {
+            let read = file.read(&mut buf)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
+        }
+
+        Ok(())
+    }
+
+    fn report(&self, progress: Option<&Sender<jobs::Report>>, status: jobs::TaskStatus) {
+        if let Some(progress) = progress {
+            let _ = progress.send(jobs::Report { name: self.name.clone(), status });
+        }
+    }
+
+    /// Copies `body` into `writer`, sending a [`jobs::TaskStatus::Downloading`]
+    /// report after every chunk so progress can be rendered as it happens.
+    fn stream_to(
+        &self,
+        mut body: reqwest::blocking::Response,
+        writer: &mut impl Write,
+        progress: Option<&Sender<jobs::Report>>,
+    ) -> Result<()> {
+        let total = body.content_length();
+        let mut buf = [0u8; 64 * 1024];
+        let mut transferred = 0u64;
+
+        loop {
+            let read = body.read(&mut buf).map_err(|e| Error::DownloadImage(e.to_string()))?;
+            if read == 0 {
+                break;
+            }
+            writer.write_all(&buf[..read])?;
+            transferred += read as u64;
+            self.report(progress, jobs::TaskStatus::Downloading { transferred, total });
+        }
+
+        Ok(())
+    }
 }
 
 impl PartialEq for Image<'_> {
@@ -175,6 +564,53 @@ impl Images<'_> {
             .get(name.as_ref())
             .ok_or_else(|| Error::UnknownImage(name.as_ref().to_string()).into())
     }
+
+    /// Pulls every image in this set across up to `jobs` worker threads,
+    /// rendering a combined progress view instead of `pull`'s bare
+    /// `println!`. Every image is attempted regardless of earlier failures;
+    /// on any failure, returns a single error listing all of them.
+    pub fn pull_all(self, jobs: usize) -> Result<()> {
+        let images: Vec<Image> = self.into_iter().collect();
+        let results = jobs::run(images, jobs, |image| image.name.clone(), |image, report| {
+            image.pull_reporting(Some(report)).map(|_| ())
+        });
+
+        let failures: Vec<String> = results
+            .into_iter()
+            .filter_map(|(image, result)| result.err().map(|e| format!("{}: {:#}", image.name, e)))
+            .collect();
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            bail!(failures.join("\n"))
+        }
+    }
+
+    /// Ensures every name in `required` is present and up to date, pulling
+    /// whichever are missing or outdated (reusing [`pull_all`](Self::pull_all)'s
+    /// concurrent, verified download path). Returns the names that were
+    /// actually fetched. Fails without pulling anything if `required` names
+    /// an image this set doesn't know about at all, so a caller such as VM
+    /// creation can treat "images just appear" as an atomic precondition.
+    pub fn pull_missing(self, required: &BTreeSet<String>) -> Result<BTreeSet<String>> {
+        let available_names = self.names();
+        for name in required {
+            if !available_names.contains(name) {
+                bail!(Error::UnknownImage(name.to_owned()));
+            }
+        }
+
+        let to_pull =
+            self.filter(|image| required.contains(&image.name) && (!image.exists() || image.outdate()));
+        let fetched = to_pull.names();
+
+        if !fetched.is_empty() {
+            to_pull.pull_all(fetched.len())?;
+        }
+
+        Ok(fetched)
+    }
 }
 
 impl<'a> IntoIterator for Images<'a> {
@@ -207,6 +643,87 @@ struct DeserializeImage {
     change: Vec<String>,
     update_after_days: Option<u64>,
     arch_mapping: Option<BTreeMap<String, String>>,
+    checksum: Option<String>,
+    checksum_url: Option<String>,
+    auth: Option<DeserializeAuth>,
+    #[serde(default)]
+    transform: Vec<DeserializeTransform>,
+}
+
+/// One step of a post-download pipeline, applied in order to the verified
+/// `.part` file before it is renamed into place.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(deny_unknown_fields)]
+struct DeserializeTransform {
+    decompress: Option<String>,
+    convert_to: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(deny_unknown_fields)]
+pub struct DeserializeAuth {
+    bearer: Option<DeserializeBearerAuth>,
+    basic: Option<DeserializeBasicAuth>,
+    netrc: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(deny_unknown_fields)]
+struct DeserializeBearerAuth {
+    token: Option<String>,
+    token_file: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(deny_unknown_fields)]
+struct DeserializeBasicAuth {
+    user: String,
+    password: Option<String>,
+    password_file: Option<PathBuf>,
+}
+
+/// Looks up `host`'s entry in a `netrc`-style credentials file and returns
+/// its login/password as a [`Credential::Basic`].
+fn netrc_credential(path: &Path, host: &str) -> Result<Option<Credential>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read netrc file `{}`", path.display()))?;
+    let tokens: Vec<&str> = content.split_whitespace().collect();
+
+    let mut current_machine: Option<&str> = None;
+    let mut login: Option<&str> = None;
+    let mut password: Option<&str> = None;
+    let mut matched = None;
+
+    let mut take_match = |current_machine: Option<&str>, login: Option<&str>, password: Option<&str>| {
+        if current_machine == Some(host) {
+            if let (Some(login), Some(password)) = (login, password) {
+                matched = Some((login.to_string(), password.to_string()));
+            }
+        }
+    };
+
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i] {
+            "machine" => {
+                take_match(current_machine, login, password);
+                current_machine = tokens.get(i + 1).copied();
+                login = None;
+                password = None;
+            }
+            "login" => login = tokens.get(i + 1).copied(),
+            "password" => password = tokens.get(i + 1).copied(),
+            _ => {}
+        }
+        i += 1;
+    }
+    take_match(current_machine, login, password);
+
+    Ok(matched.map(|(user, password)| Credential::Basic { user, password }))
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -283,9 +800,50 @@ fn update_images(
                 } else {
                     new.arch_mapping.to_owned()
                 };
+                let checksum = if change_set.contains("keep-checksum")
+                    || !update_all && !change_set.contains("update-checksum")
+                {
+                    old.checksum.to_owned()
+                } else {
+                    new.checksum.to_owned()
+                };
+                let checksum_url = if change_set.contains("keep-checksum-url")
+                    || !update_all && !change_set.contains("update-checksum-url")
+                {
+                    old.checksum_url.to_owned()
+                } else {
+                    new.checksum_url.to_owned()
+                };
+                // Auth is kept from the local file unless `update-auth` is
+                // listed explicitly (`update-all` does not cover it): secrets
+                // (inline tokens/passwords, or paths to files holding them)
+                // must never be silently replaced by the embedded defaults.
+                let auth = if change_set.contains("update-auth") {
+                    new.auth.to_owned()
+                } else {
+                    old.auth.to_owned()
+                };
+                let transform = if change_set.contains("keep-transform")
+                    || !update_all && !change_set.contains("update-transform")
+                {
+                    old.transform.to_owned()
+                } else {
+                    new.transform.to_owned()
+                };
                 images.insert(
                     old_name.to_owned(),
-                    DeserializeImage { url, get_url_prog, description, change, update_after_days, arch_mapping },
+                    DeserializeImage {
+                        url,
+                        get_url_prog,
+                        description,
+                        change,
+                        update_after_days,
+                        arch_mapping,
+                        checksum,
+                        checksum_url,
+                        auth,
+                        transform,
+                    },
                 );
                 embedded_image = embedded_images.next();
                 config_image = config_images.next();
@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::thread;
+
+/// Where a single task currently stands in its lifecycle.
+#[derive(Clone, Debug)]
+pub enum TaskStatus {
+    Queued,
+    Downloading { transferred: u64, total: Option<u64> },
+    Verifying,
+    Done,
+    Failed(String),
+}
+
+impl std::fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskStatus::Queued => write!(f, "queued"),
+            TaskStatus::Downloading { transferred, total: Some(total) } => {
+                write!(f, "downloading {}/{} bytes", transferred, total)
+            }
+            TaskStatus::Downloading { transferred, total: None } => {
+                write!(f, "downloading {} bytes", transferred)
+            }
+            TaskStatus::Verifying => write!(f, "verifying checksum"),
+            TaskStatus::Done => write!(f, "done"),
+            TaskStatus::Failed(message) => write!(f, "failed: {}", message),
+        }
+    }
+}
+
+/// A progress update a worker sends about one of its tasks.
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub name: String,
+    pub status: TaskStatus,
+}
+
+/// Runs `work` over `items` across up to `jobs` worker threads, rendering a
+/// multi-line progress view from the `Report`s each task sends as it goes.
+///
+/// Every item runs to completion regardless of earlier failures; results are
+/// returned paired with their item, in the order of `items`, so the caller
+/// can report every failure instead of aborting on the first. A task whose
+/// `work` returns `Err` sends a [`TaskStatus::Failed`] report with `name`
+/// (via `label`) before the pool moves on, so `render_progress` shows it as
+/// failed instead of leaving it frozen on its last status.
+pub fn run<T, F>(items: Vec<T>, jobs: usize, label: impl Fn(&T) -> String, work: F) -> Vec<(T, anyhow::Result<()>)>
+where
+    T: Send,
+    F: Fn(&T, &Sender<Report>) -> anyhow::Result<()> + Sync,
+{
+    let jobs = jobs.max(1);
+    let queue = Mutex::new(items.into_iter().enumerate().collect::<Vec<_>>());
+    let results = Mutex::new(BTreeMap::new());
+    let (report_tx, report_rx) = mpsc::channel::<Report>();
+
+    thread::scope(|scope| {
+        scope.spawn(move || render_progress(report_rx));
+
+        for _ in 0..jobs {
+            let queue = &queue;
+            let work = &work;
+            let label = &label;
+            let results = &results;
+            let report_tx = report_tx.clone();
+            scope.spawn(move || loop {
+                let next = queue.lock().unwrap().pop();
+                let (index, item) = match next {
+                    Some(next) => next,
+                    None => break,
+                };
+                let result = work(&item, &report_tx);
+                if let Err(err) = &result {
+                    let _ = report_tx.send(Report {
+                        name: label(&item),
+                        status: TaskStatus::Failed(format!("{:#}", err)),
+                    });
+                }
+                results.lock().unwrap().insert(index, (item, result));
+            });
+        }
+
+        drop(report_tx);
+    });
+
+    results.into_inner().unwrap().into_values().collect()
+}
+
+/// Runs `work` over `items` across up to `jobs` worker threads with no
+/// progress reporting, collecting every result instead of aborting on the
+/// first failure. Used to fan the per-VM `main` commands (`start`, `stop`,
+/// `ssh`, ...) out in parallel.
+pub fn run_simple<T, F>(items: Vec<T>, jobs: usize, work: F) -> Vec<(T, anyhow::Result<()>)>
+where
+    T: Send,
+    F: Fn(&mut T) -> anyhow::Result<()> + Sync,
+{
+    let jobs = jobs.max(1);
+    let queue = Mutex::new(items.into_iter().enumerate().collect::<Vec<_>>());
+    let results = Mutex::new(BTreeMap::new());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            let queue = &queue;
+            let work = &work;
+            let results = &results;
+            scope.spawn(move || loop {
+                let next = queue.lock().unwrap().pop();
+                let (index, mut item) = match next {
+                    Some(next) => next,
+                    None => break,
+                };
+                let result = work(&mut item);
+                results.lock().unwrap().insert(index, (item, result));
+            });
+        }
+    });
+
+    results.into_inner().unwrap().into_values().collect()
+}
+
+fn render_progress(reports: Receiver<Report>) {
+    let mut last: BTreeMap<String, TaskStatus> = BTreeMap::new();
+    let mut printed_lines = 0usize;
+
+    for report in reports {
+        last.insert(report.name, report.status);
+
+        if printed_lines > 0 {
+            print!("\x1b[{}A", printed_lines);
+        }
+        for (name, status) in &last {
+            println!("\x1b[2K{}: {}", name, status);
+        }
+        printed_lines = last.len();
+    }
+}
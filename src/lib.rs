@@ -1,7 +1,12 @@
 mod cache;
 pub mod cli;
 pub mod config;
+pub mod display;
 mod errors;
+mod jobs;
+mod lua_hooks;
+pub mod metrics;
+pub mod qmp;
 mod specified_by;
 mod string_like;
 mod vm;
@@ -9,5 +14,7 @@ mod vm_config;
 mod vms_creator;
 
 pub use errors::{Error, Result};
+pub use jobs::run_simple;
+pub use lua_hooks::{run_build_hook, HookContext};
 pub use vm::VM;
 pub use vms_creator::{VMsCreator, WithPid};
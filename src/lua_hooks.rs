@@ -0,0 +1,67 @@
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use anyhow::Context as _;
+use mlua::Lua;
+
+use crate::errors::Error;
+use crate::Result;
+
+/// The subset of a VM's configuration exposed to a `.lua` build hook as the
+/// `vm` table, so scripts can shape their QEMU arguments around it.
+pub struct HookContext<'a> {
+    pub name: &'a str,
+    pub memory: u32,
+    pub disks: &'a [String],
+    pub taps: &'a [String],
+}
+
+/// Runs the build hook at `script_path` and returns `base_args` with
+/// whatever the script appended tacked on at the end.
+///
+/// The script sees two globals: `vm`, a table mirroring `ctx` (`name`,
+/// `memory`, `disks`, `taps`), and `builder`, whose `arg(...)` method
+/// appends one or more raw QEMU arguments after vml's own computed ones.
+/// This lets a VM declare bespoke devices — audio, SPICE, vfio passthrough,
+/// a custom `-netdev` — without patching the crate.
+pub fn run_build_hook(
+    script_path: &Path,
+    ctx: &HookContext,
+    base_args: &[String],
+) -> Result<Vec<String>> {
+    let lua = Lua::new();
+    let args = Rc::new(RefCell::new(base_args.to_vec()));
+
+    let vm_table = lua.create_table().map_err(lua_error)?;
+    vm_table.set("name", ctx.name).map_err(lua_error)?;
+    vm_table.set("memory", ctx.memory).map_err(lua_error)?;
+    vm_table.set("disks", ctx.disks.to_vec()).map_err(lua_error)?;
+    vm_table.set("taps", ctx.taps.to_vec()).map_err(lua_error)?;
+    lua.globals().set("vm", vm_table).map_err(lua_error)?;
+
+    let builder = lua.create_table().map_err(lua_error)?;
+    let builder_args = Rc::clone(&args);
+    let arg_fn = lua
+        .create_function(move |_, values: mlua::Variadic<String>| {
+            builder_args.borrow_mut().extend(values);
+            Ok(())
+        })
+        .map_err(lua_error)?;
+    builder.set("arg", arg_fn).map_err(lua_error)?;
+    lua.globals().set("builder", builder).map_err(lua_error)?;
+
+    let script = std::fs::read_to_string(script_path)
+        .with_context(|| format!("failed to read build hook `{}`", script_path.display()))?;
+    lua.load(&script)
+        .set_name(&script_path.to_string_lossy())
+        .exec()
+        .map_err(lua_error)?;
+
+    let result = args.borrow().clone();
+    Ok(result)
+}
+
+fn lua_error(error: mlua::Error) -> anyhow::Error {
+    Error::LuaHook(error.to_string()).into()
+}
@@ -2,7 +2,9 @@ use std::collections::BTreeSet;
 use std::env;
 use std::fs;
 use std::io;
-use std::process::Command;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
 use std::thread;
 use std::time::Duration;
 
@@ -11,6 +13,7 @@ use clap::ArgMatches;
 use vml::cli;
 use vml::config::Config;
 use vml::config::CreateExistsAction;
+use vml::display::{DisplaySettings, LookingGlass, SpiceTarget};
 use vml::files;
 use vml::template;
 use vml::{Error, Result};
@@ -45,7 +48,11 @@ fn create(config: &Config, create_matches: &ArgMatches) -> Result<()> {
         vec![]
     };
 
-    let image = create_matches.value_of("image");
+    // `-i/--image` may be repeated to declare the full set of images this
+    // creation depends on (the "bound images" a VM is guaranteed to have
+    // before it starts); the first one is still what the new VM's disk is
+    // built from, the rest are pulled alongside it.
+    let images: Vec<&str> = create_matches.values_of("image").map(Iterator::collect).unwrap_or_default();
 
     let exists = if create_matches.is_present("exists-fail") {
         CreateExistsAction::Fail
@@ -57,14 +64,71 @@ fn create(config: &Config, create_matches: &ArgMatches) -> Result<()> {
         config.commands.create.exists
     };
 
+    if !images.is_empty() {
+        let required: BTreeSet<String> = images.iter().map(|image| image.to_string()).collect();
+        let fetched = vml::images::available(&config.images)?.pull_missing(&required)?;
+        for name in &fetched {
+            println!("Pulled image {}", name);
+        }
+    }
+
     for name in names {
-        vml::create_vm(&config, name, image, exists)?;
+        vml::create_vm(&config, name, images.first().copied(), exists)?;
+    }
+
+    Ok(())
+}
+
+/// Runs `work` on every item across up to `jobs` worker threads, letting
+/// every item finish regardless of earlier failures, then reports all
+/// failures together (labelled via `label`) instead of aborting on the
+/// first one.
+fn fan_out<T, F>(items: Vec<T>, jobs: usize, label: impl Fn(&T) -> String, work: F) -> Result<()>
+where
+    T: Send,
+    F: Fn(&mut T) -> Result<()> + Sync,
+{
+    let mut failures: Vec<String> = vml::run_simple(items, jobs, &work)
+        .into_iter()
+        .filter_map(|(item, result)| result.err().map(|e| format!("{}: {:#}", label(&item), e)))
+        .collect();
+    failures.sort();
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Multiple(failures).into())
+    }
+}
+
+/// Like [`fan_out`], but for per-VM commands that produce output to show the
+/// user: `work` returns `Some` line to buffer instead of printing it live,
+/// and every buffered line is printed together, sorted by `label`, once the
+/// whole pool has drained. Keeps list/show-style output stable instead of
+/// interleaving nondeterministically across worker threads.
+fn fan_out_output<T, F>(items: Vec<T>, jobs: usize, label: impl Fn(&T) -> String, work: F) -> Result<()>
+where
+    T: Send,
+    F: Fn(&mut T) -> Result<Option<String>> + Sync,
+{
+    let output: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+    fan_out(items, jobs, &label, |item| {
+        if let Some(line) = work(item)? {
+            output.lock().unwrap().push((label(item), line));
+        }
+        Ok(())
+    })?;
+
+    let mut output = output.into_inner().unwrap();
+    output.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, line) in output {
+        println!("{}: {}", name, line);
     }
 
     Ok(())
 }
 
-fn start(config: &Config, start_matches: &ArgMatches, vmc: &mut VMsCreator) -> Result<()> {
+fn start(config: &Config, start_matches: &ArgMatches, vmc: &mut VMsCreator, jobs: usize) -> Result<()> {
     set_specifications(vmc, start_matches);
 
     let wait_ssh = start_matches.is_present("wait-ssh");
@@ -76,15 +140,19 @@ fn start(config: &Config, start_matches: &ArgMatches, vmc: &mut VMsCreator) -> R
     } else {
         vec![]
     };
+    // Skips each VM's `.lua` build hook, if it has one, for debugging a
+    // QEMU command line without the script's customizations in the way.
+    let no_scripts = start_matches.is_present("no-scripts");
+    let display = display_settings(start_matches)?;
 
     vmc.with_pid(WithPid::Without);
     vmc.error_on_empty();
 
     let vms = vmc.create()?;
 
-    for vm in &vms {
-        vm.start(cloud_init, &drives)?;
-    }
+    fan_out(vms, jobs, |vm| vm.name.clone(), |vm| {
+        vm.start(cloud_init, &drives, no_scripts, &display)
+    })?;
 
     if wait_ssh {
         let user: Option<&str> = None;
@@ -95,20 +163,64 @@ fn start(config: &Config, start_matches: &ArgMatches, vmc: &mut VMsCreator) -> R
             format!("ConnectTimeout={}", config.commands.start.wait_ssh.timeout),
         ];
         let flags: Vec<&str> = vec![];
-        for vm in &vms {
-            for _ in 0..repeat {
-                if vm.ssh(&user, &options, &flags, &Some(vec!["true"]))? == Some(0) {
-                    break;
-                } else {
+
+        // Each VM independently sleeps/retries until SSH answers, so the
+        // worst case for the whole batch is a single max(timeouts) instead
+        // of their sum.
+        let vms = vmc.create()?;
+        fan_out(
+            vms,
+            jobs,
+            |vm| vm.name.clone(),
+            |vm| {
+                for _ in 0..repeat {
+                    if vm.ssh(&user, &options, &flags, &Some(vec!["true"]))? == Some(0) {
+                        return Ok(());
+                    }
                     thread::sleep(Duration::from_secs(sleep));
                 }
-            }
-        }
+                Ok(())
+            },
+        )?;
     }
 
     Ok(())
 }
 
+/// Builds the requested display/audio backends from `start`'s flags,
+/// leaving everything unset (today's headless default) when none are
+/// passed. Per-VM config keys for the same settings are merged in by
+/// `vm.start` itself.
+fn display_settings(start_matches: &ArgMatches) -> Result<DisplaySettings> {
+    let spice = if let Some(socket) = start_matches.value_of("spice-socket") {
+        Some(SpiceTarget::UnixSocket(PathBuf::from(socket)))
+    } else if let Some(port) = start_matches.value_of("spice-port") {
+        Some(SpiceTarget::Port(port.parse()?))
+    } else {
+        None
+    };
+
+    let pulseaudio = start_matches.is_present("pulseaudio");
+
+    let looking_glass = if start_matches.is_present("looking-glass") {
+        let width = start_matches
+            .value_of("looking-glass-width")
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or(1920);
+        let height = start_matches
+            .value_of("looking-glass-height")
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or(1080);
+        Some(LookingGlass { width, height })
+    } else {
+        None
+    };
+
+    Ok(DisplaySettings { spice, pulseaudio, looking_glass })
+}
+
 fn confirm(message: &str) -> bool {
     println!("{}", message);
     let mut input = String::new();
@@ -119,15 +231,16 @@ fn confirm(message: &str) -> bool {
     matches!(input.as_str(), "y" | "yes")
 }
 
+/// Strips every `--host`/`-H` and `--host-group` flag, and its value, from
+/// argv, so the remaining args can be replayed on each remote host without
+/// re-triggering multi-host dispatch there.
 fn args_without_host() -> Vec<String> {
     let mut args: Vec<String> = Vec::new();
     let mut args_iterator = env::args();
     let mut optional_arg = args_iterator.next();
-    let mut found = false;
     while let Some(arg) = &optional_arg {
-        if !found && matches!(arg.as_str(), "--host" | "-H") {
+        if matches!(arg.as_str(), "--host" | "-H" | "--host-group") {
             args_iterator.next();
-            found = true;
         } else {
             args.push(arg.to_string());
         }
@@ -137,6 +250,65 @@ fn args_without_host() -> Vec<String> {
     args
 }
 
+/// Resolves the target hosts for this invocation from repeated `--host`
+/// flags and/or a `--host-group NAME` looked up in the config.
+fn resolve_hosts(matches: &ArgMatches, config: &Config) -> Result<Vec<String>> {
+    let mut hosts: Vec<String> =
+        matches.values_of("host").map(|values| values.map(str::to_string).collect()).unwrap_or_default();
+
+    if let Some(group) = matches.value_of("host-group") {
+        let members = config
+            .host_groups
+            .get(group)
+            .ok_or_else(|| Error::UnknownHostGroup(group.to_string()))?;
+        hosts.extend(members.iter().cloned());
+    }
+
+    Ok(hosts)
+}
+
+/// Re-executes `args` on `host` over `ssh`, prefixing every line of its
+/// output with the host name so interleaved output from several hosts
+/// stays attributable.
+fn run_on_host(host: &mut String, args: &[String], is_ssh: bool) -> Result<()> {
+    let mut ssh = Command::new("ssh");
+    if is_ssh {
+        ssh.arg("-t");
+    }
+    ssh.arg(host.as_str()).args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let mut child = ssh.spawn().map_err(|e| Error::executable("ssh", &e.to_string()))?;
+    let stdout = child.stdout.take().expect("child stdout was piped");
+    let stderr = child.stderr.take().expect("child stderr was piped");
+
+    let out_host = host.clone();
+    let out_thread = thread::spawn(move || prefix_lines(&out_host, stdout, false));
+    let err_host = host.clone();
+    let err_thread = thread::spawn(move || prefix_lines(&err_host, stderr, true));
+
+    let status = child.wait()?;
+    out_thread.join().ok();
+    err_thread.join().ok();
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::executable("ssh", format!("exited with {}", status)).into())
+    }
+}
+
+fn prefix_lines(host: &str, reader: impl io::Read, is_stderr: bool) {
+    use io::BufRead;
+
+    for line in io::BufReader::new(reader).lines().flatten() {
+        if is_stderr {
+            eprintln!("[{}] {}", host, line);
+        } else {
+            println!("[{}] {}", host, line);
+        }
+    }
+}
+
 fn parse_user_at_name(user_at_name: &str) -> (Option<&str>, &str) {
     if user_at_name.contains('@') {
         let user_name: Vec<&str> = user_at_name.splitn(2, '@').collect();
@@ -178,19 +350,20 @@ fn main() -> Result<()> {
     let matches = cli::build_cli().get_matches();
     let config = Config::new()?;
 
-    if let Some(host) = matches.value_of("host") {
-        let args: Vec<String> = args_without_host();
-        let mut ssh = Command::new("ssh");
-        if matches.subcommand_matches("ssh").is_some() {
-            ssh.arg("-t");
-        }
-        ssh.arg(&host).args(&args);
+    let hosts = resolve_hosts(&matches, &config)?;
+    if !hosts.is_empty() {
+        let args = args_without_host();
+        let is_ssh = matches.subcommand_matches("ssh").is_some();
+        let jobs = hosts.len();
 
-        ssh.spawn().map_err(|e| Error::executable("ssh", &e.to_string()))?.wait()?;
+        fan_out(hosts, jobs, |host| host.clone(), |host| run_on_host(host, &args, is_ssh))?;
 
         return Ok(());
     }
 
+    let jobs: usize =
+        matches.value_of("jobs").map(str::parse).transpose()?.unwrap_or(config.commands.jobs);
+
     files::install_all(&config)?;
     let mut vmc = VMsCreator::new(&config);
     if matches.is_present("all-vms") {
@@ -230,22 +403,33 @@ fn main() -> Result<()> {
                     let image_template = store_images_matches.value_of("image");
                     let force = store_images_matches.is_present("force");
 
-                    for vm in vmc.create()? {
-                        let image = if let Some(template) = image_template {
-                            template::render(&vm.context(), template, "main: image store (image)")?
-                        } else {
-                            vm.hyphenized()
-                        };
-                        vm.store_disk(&config.images.directory.join(&image), force)?;
-                    }
+                    fan_out(
+                        vmc.create()?,
+                        jobs,
+                        |vm| vm.name.clone(),
+                        |vm| {
+                            let image = if let Some(template) = image_template {
+                                template::render(&vm.context(), template, "main: image store (image)")?
+                            } else {
+                                vm.hyphenized()
+                            };
+                            vm.store_disk(&config.images.directory.join(&image), force)
+                        },
+                    )?;
                 }
 
                 Some(("pull", pull_images_matches)) => {
-                    let images = pull_images_matches.values_of("IMAGES").unwrap();
-
-                    for image in images {
-                        vml::images::pull(images_dir, image)?;
-                    }
+                    let names: BTreeSet<&str> =
+                        pull_images_matches.values_of("IMAGES").unwrap().collect();
+                    let jobs: usize = pull_images_matches
+                        .value_of("jobs")
+                        .map(str::parse)
+                        .transpose()?
+                        .unwrap_or(config.commands.image.pull.jobs);
+
+                    let available = vml::images::available(&config.images)?;
+                    let requested = available.filter(|image| names.contains(image.name.as_str()));
+                    requested.pull_all(jobs)?;
                 }
 
                 _ => println!("Unexpected images command"),
@@ -254,11 +438,11 @@ fn main() -> Result<()> {
 
         Some(("create", create_matches)) => create(&config, &create_matches)?,
 
-        Some(("start", start_matches)) => start(&config, &start_matches, &mut vmc)?,
+        Some(("start", start_matches)) => start(&config, &start_matches, &mut vmc, jobs)?,
 
         Some(("run", run_matches)) => {
             create(&config, &run_matches)?;
-            start(&config, &run_matches, &mut vmc)?;
+            start(&config, &run_matches, &mut vmc, jobs)?;
         }
 
         Some(("stop", stop_matches)) => {
@@ -269,9 +453,7 @@ fn main() -> Result<()> {
             vmc.with_pid(WithPid::Filter);
             vmc.error_on_empty();
 
-            for mut vm in vmc.create()? {
-                vm.stop(force)?;
-            }
+            fan_out(vmc.create()?, jobs, |vm| vm.name.clone(), |vm| vm.stop(force))?;
         }
 
         Some(("ssh", ssh_matches)) => {
@@ -305,13 +487,18 @@ fn main() -> Result<()> {
                 vmc.with_pid(WithPid::Error);
             }
             vmc.error_on_empty();
-            for vm in vmc.create()? {
-                if vm.ssh(&user, &ssh_options, &ssh_flags, &cmd)? != Some(0)
-                    && ssh_matches.is_present("check")
-                {
-                    return Err(Error::SSHFailed(vm.name));
-                }
-            }
+            let check = ssh_matches.is_present("check");
+            fan_out(
+                vmc.create()?,
+                jobs,
+                |vm| vm.name.clone(),
+                |vm| {
+                    if vm.ssh(&user, &ssh_options, &ssh_flags, &cmd)? != Some(0) && check {
+                        return Err(Error::SSHFailed(vm.name.clone()).into());
+                    }
+                    Ok(())
+                },
+            )?;
         }
 
         Some(("rsync-to", rsync_to_matches)) => {
@@ -355,13 +542,19 @@ fn main() -> Result<()> {
             vmc.error_on_empty();
             if let Some(sources) = sources {
                 let sources: Vec<&str> = sources.collect();
-                for vm in vmc.create()? {
-                    vm.rsync_to(&user, &rsync_options, &sources, &destination)?;
-                }
+                fan_out(
+                    vmc.create()?,
+                    jobs,
+                    |vm| vm.name.clone(),
+                    |vm| vm.rsync_to(&user, &rsync_options, &sources, &destination),
+                )?;
             } else if let Some(template) = template {
-                for vm in vmc.create()? {
-                    vm.rsync_to_template(&user, &rsync_options, template, &destination)?;
-                }
+                fan_out(
+                    vmc.create()?,
+                    jobs,
+                    |vm| vm.name.clone(),
+                    |vm| vm.rsync_to_template(&user, &rsync_options, template, &destination),
+                )?;
             }
         }
 
@@ -405,9 +598,12 @@ fn main() -> Result<()> {
                 vmc.with_pid(WithPid::Error);
             }
             vmc.error_on_empty();
-            for vm in vmc.create()? {
-                vm.rsync_from(&user, &rsync_options, &sources, &destination)?;
-            }
+            fan_out(
+                vmc.create()?,
+                jobs,
+                |vm| vm.name.clone(),
+                |vm| vm.rsync_from(&user, &rsync_options, &sources, &destination),
+            )?;
         }
 
         Some(("show", show_matches)) => {
@@ -450,6 +646,7 @@ fn main() -> Result<()> {
             set_specifications(&mut vmc, monitor_matches);
 
             let command = monitor_matches.value_of("command");
+            let qmp = monitor_matches.value_of("qmp");
 
             if vmc.is_all() {
                 vmc.with_pid(WithPid::Filter);
@@ -457,13 +654,28 @@ fn main() -> Result<()> {
                 vmc.with_pid(WithPid::Error);
             }
             vmc.error_on_empty();
-            if let Some(command) = command {
-                for vm in vmc.create()? {
-                    let reply = vm.monitor_command(command)?;
-                    if let Some(reply) = reply {
-                        println!("{}", reply);
-                    }
-                }
+            if let Some(qmp) = qmp {
+                let (qmp_command, qmp_arguments) = vml::qmp::parse_command(qmp)?;
+                fan_out_output(
+                    vmc.create()?,
+                    jobs,
+                    |vm| vm.name.clone(),
+                    |vm| {
+                        let mut client = vml::qmp::QmpClient::connect(&vm.qmp_socket_path())?;
+                        let reply = client.execute(&qmp_command, qmp_arguments.clone())?;
+                        Ok(Some(serde_json::to_string_pretty(&reply)?))
+                    },
+                )?;
+            } else if let Some(command) = command {
+                // One-shot queries fan out like the other per-VM commands;
+                // the interactive monitor below stays sequential since
+                // there's no sensible way to multiplex a terminal session.
+                fan_out_output(
+                    vmc.create()?,
+                    jobs,
+                    |vm| vm.name.clone(),
+                    |vm| Ok(vm.monitor_command(command)?),
+                )?;
             } else {
                 for vm in vmc.create()? {
                     vm.monitor()?;
@@ -471,6 +683,34 @@ fn main() -> Result<()> {
             }
         }
 
+        Some(("stats", stats_matches)) => {
+            set_specifications(&mut vmc, stats_matches);
+            vmc.with_pid(WithPid::Filter);
+            vmc.error_on_empty();
+
+            let json = stats_matches.is_present("json");
+            let csv = stats_matches.is_present("csv");
+            let watch: Option<u64> = stats_matches.value_of("watch").map(str::parse).transpose()?;
+
+            loop {
+                let metrics: Vec<vml::metrics::VmMetrics> =
+                    vmc.create()?.iter().map(vml::metrics::collect).collect::<Result<Vec<_>>>()?;
+
+                if json {
+                    println!("{}", vml::metrics::to_json(&metrics)?);
+                } else if csv {
+                    print!("{}", vml::metrics::to_csv(&metrics));
+                } else {
+                    vml::metrics::print_table(&metrics);
+                }
+
+                match watch {
+                    Some(seconds) => thread::sleep(Duration::from_secs(seconds)),
+                    None => break,
+                }
+            }
+        }
+
         Some(("rm", rm_matches)) => {
             set_specifications(&mut vmc, rm_matches);
 
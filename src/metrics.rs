@@ -0,0 +1,144 @@
+use std::fs;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::qmp::QmpClient;
+use crate::Result;
+use crate::VM;
+
+/// Read/write counters for a single virtual disk, as reported by QMP's
+/// `query-blockstats`.
+#[derive(Debug, Serialize)]
+pub struct BlockStats {
+    pub device: String,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    pub read_ops: u64,
+    pub write_ops: u64,
+}
+
+/// A snapshot of one VM's runtime resource usage.
+#[derive(Debug, Serialize)]
+pub struct VmMetrics {
+    pub name: String,
+    pub disks: Vec<BlockStats>,
+    pub guest_memory_bytes: Option<u64>,
+    pub host_cpu_seconds: Option<f64>,
+}
+
+/// Gathers a snapshot of `vm`'s runtime usage over its QMP socket. Counters
+/// a given QEMU build doesn't expose (e.g. no balloon device) come back as
+/// `None` rather than failing the whole snapshot.
+pub fn collect(vm: &VM) -> Result<VmMetrics> {
+    let mut client = QmpClient::connect(&vm.qmp_socket_path())?;
+
+    let blockstats = client.execute("query-blockstats", None)?;
+    let disks = parse_blockstats(&blockstats);
+
+    let guest_memory_bytes = client
+        .execute("query-balloon", None)
+        .ok()
+        .and_then(|reply| reply.get("actual").and_then(Value::as_u64));
+
+    let host_cpu_seconds = vm.pid().and_then(host_cpu_seconds);
+
+    Ok(VmMetrics { name: vm.name.clone(), disks, guest_memory_bytes, host_cpu_seconds })
+}
+
+fn parse_blockstats(reply: &Value) -> Vec<BlockStats> {
+    reply
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    let device = entry.get("device").and_then(Value::as_str)?.to_string();
+                    let stats = entry.get("stats")?;
+                    Some(BlockStats {
+                        device,
+                        bytes_read: stats.get("rd_bytes").and_then(Value::as_u64).unwrap_or(0),
+                        bytes_written: stats.get("wr_bytes").and_then(Value::as_u64).unwrap_or(0),
+                        read_ops: stats.get("rd_operations").and_then(Value::as_u64).unwrap_or(0),
+                        write_ops: stats.get("wr_operations").and_then(Value::as_u64).unwrap_or(0),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reads the host-side CPU time QEMU has accumulated for `pid`, in seconds,
+/// from `/proc/<pid>/stat`.
+fn host_cpu_seconds(pid: u32) -> Option<f64> {
+    let contents = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // Field 2 (comm) can itself contain spaces, so resume splitting after
+    // its closing paren instead of trusting fixed field indices from 0.
+    let after_comm = contents.rsplit_once(')')?.1;
+    let fields: Vec<&str> = after_comm.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    let ticks_per_second = 100.0;
+    Some((utime + stime) as f64 / ticks_per_second)
+}
+
+/// Prints `metrics` as a table, one row per VM/disk pair, with the name and
+/// disk columns left-aligned and the numeric columns right-aligned.
+pub fn print_table(metrics: &[VmMetrics]) {
+    println!(
+        "{:<20} {:<16} {:>14} {:>14} {:>12} {:>10}",
+        "NAME", "DISK", "READ", "WRITTEN", "MEM", "CPU(s)"
+    );
+    for vm in metrics {
+        let memory =
+            vm.guest_memory_bytes.map(|bytes| bytes.to_string()).unwrap_or_else(|| "-".to_string());
+        let cpu =
+            vm.host_cpu_seconds.map(|secs| format!("{:.1}", secs)).unwrap_or_else(|| "-".to_string());
+
+        if vm.disks.is_empty() {
+            println!("{:<20} {:<16} {:>14} {:>14} {:>12} {:>10}", vm.name, "-", "-", "-", memory, cpu);
+            continue;
+        }
+        for disk in &vm.disks {
+            println!(
+                "{:<20} {:<16} {:>14} {:>14} {:>12} {:>10}",
+                vm.name, disk.device, disk.bytes_read, disk.bytes_written, memory, cpu
+            );
+        }
+    }
+}
+
+/// Serializes `metrics` as a JSON array.
+pub fn to_json(metrics: &[VmMetrics]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(metrics)?)
+}
+
+/// Serializes `metrics` as CSV, one row per VM/disk pair.
+pub fn to_csv(metrics: &[VmMetrics]) -> String {
+    let mut out = String::from(
+        "name,disk,bytes_read,bytes_written,read_ops,write_ops,guest_memory_bytes,host_cpu_seconds\n",
+    );
+    for vm in metrics {
+        let memory = vm.guest_memory_bytes.map(|bytes| bytes.to_string()).unwrap_or_default();
+        let cpu = vm.host_cpu_seconds.map(|secs| format!("{:.3}", secs)).unwrap_or_default();
+
+        if vm.disks.is_empty() {
+            out.push_str(&format!("{},,,,,,{},{}\n", vm.name, memory, cpu));
+            continue;
+        }
+        for disk in &vm.disks {
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                vm.name,
+                disk.device,
+                disk.bytes_read,
+                disk.bytes_written,
+                disk.read_ops,
+                disk.write_ops,
+                memory,
+                cpu
+            ));
+        }
+    }
+    out
+}
@@ -0,0 +1,111 @@
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+use anyhow::{bail, Context as _};
+use serde_json::{json, Value};
+
+use crate::errors::Error;
+use crate::Result;
+
+/// A connected QMP (QEMU Machine Protocol) session on a VM's monitor socket.
+///
+/// Handles the greeting/`qmp_capabilities` handshake and line-delimited JSON
+/// framing, so callers just send a command and get back its `return` value;
+/// interleaved `event` objects are skipped transparently.
+pub struct QmpClient {
+    stream: BufReader<UnixStream>,
+}
+
+impl QmpClient {
+    /// Connects to `socket_path` and completes the handshake every QMP
+    /// session must do before any other command is accepted.
+    pub fn connect(socket_path: &Path) -> Result<Self> {
+        let stream = UnixStream::connect(socket_path).with_context(|| {
+            format!("failed to connect to QMP socket `{}`", socket_path.display())
+        })?;
+        let mut client = QmpClient { stream: BufReader::new(stream) };
+
+        let greeting = client.read_object()?;
+        if greeting.get("QMP").is_none() {
+            bail!(Error::QmpProtocol("socket did not send a QMP greeting".to_string()));
+        }
+
+        client.execute("qmp_capabilities", None)?;
+        Ok(client)
+    }
+
+    /// Sends `{"execute": command, "arguments": arguments}` and returns the
+    /// matching `return` value.
+    pub fn execute(&mut self, command: &str, arguments: Option<Value>) -> Result<Value> {
+        let mut request = json!({ "execute": command });
+        if let Some(arguments) = arguments {
+            request["arguments"] = arguments;
+        }
+        self.send(&request)?;
+
+        loop {
+            let reply = self.read_object()?;
+            if reply.get("event").is_some() {
+                continue;
+            }
+            if let Some(error) = reply.get("error") {
+                let class = error.get("class").and_then(Value::as_str).unwrap_or("GenericError");
+                let desc = error.get("desc").and_then(Value::as_str).unwrap_or("unknown error");
+                bail!(Error::QmpError { class: class.to_string(), desc: desc.to_string() });
+            }
+            return Ok(reply.get("return").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    fn send(&mut self, request: &Value) -> Result<()> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        self.stream.get_mut().write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    fn read_object(&mut self) -> Result<Value> {
+        let mut line = String::new();
+        let read = self.stream.read_line(&mut line)?;
+        if read == 0 {
+            bail!(Error::QmpProtocol("QMP socket closed unexpectedly".to_string()));
+        }
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+/// Parses a `--qmp` argument into a `(command, arguments)` pair.
+///
+/// Accepts a full QMP request object (`{"execute":"query-status"}`) or a
+/// shorthand: a bare command name, optionally followed by a JSON arguments
+/// object (`query-status`, or `device_add {"driver":"e1000"}`).
+pub fn parse_command(input: &str) -> Result<(String, Option<Value>)> {
+    let input = input.trim();
+    if input.starts_with('{') {
+        let value: Value =
+            serde_json::from_str(input).with_context(|| format!("invalid QMP JSON `{}`", input))?;
+        let command = value
+            .get("execute")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::QmpProtocol(format!("missing `execute` in `{}`", input)))?
+            .to_string();
+        return Ok((command, value.get("arguments").cloned()));
+    }
+
+    match input.split_once(char::is_whitespace) {
+        Some((command, rest)) => {
+            let rest = rest.trim();
+            let arguments = if rest.is_empty() {
+                None
+            } else {
+                Some(
+                    serde_json::from_str(rest)
+                        .with_context(|| format!("invalid QMP arguments `{}`", rest))?,
+                )
+            };
+            Ok((command.to_string(), arguments))
+        }
+        None => Ok((input.to_string(), None)),
+    }
+}